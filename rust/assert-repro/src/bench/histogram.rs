@@ -0,0 +1,78 @@
+/// A streaming latency histogram, bucketed by power-of-two microseconds.
+///
+/// Keeping per-bucket counts instead of the raw samples is what makes this
+/// "streaming": memory use is a handful of `u64` counters regardless of how
+/// many operations are recorded, which matters once a workload runs for
+/// millions of ops.
+pub struct LatencyHistogram {
+    // buckets[i] counts samples in [2^i, 2^(i+1)) microseconds; bucket 0
+    // also catches anything below 1us.
+    buckets: Vec<u64>,
+    count: u64,
+}
+
+const NUM_BUCKETS: usize = 32;
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        LatencyHistogram {
+            buckets: vec![0; NUM_BUCKETS],
+            count: 0,
+        }
+    }
+
+    pub fn record(&mut self, latency: std::time::Duration) {
+        let micros = latency.as_micros().max(1) as u64;
+        let bucket = (63 - micros.leading_zeros()) as usize;
+        let bucket = bucket.min(NUM_BUCKETS - 1);
+        self.buckets[bucket] += 1;
+        self.count += 1;
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (a, b) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *a += b;
+        }
+        self.count += other.count;
+    }
+
+    /// Approximate latency, in microseconds, at the given percentile
+    /// (e.g. `0.5` for p50, `0.99` for p99). Returns 0 if no samples were
+    /// recorded.
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = (self.count as f64 * p).ceil() as u64;
+        let mut seen = 0u64;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return 1u64 << i;
+            }
+        }
+        1u64 << (NUM_BUCKETS - 1)
+    }
+
+    pub fn p50(&self) -> u64 {
+        self.percentile(0.50)
+    }
+
+    pub fn p99(&self) -> u64 {
+        self.percentile(0.99)
+    }
+
+    pub fn p999(&self) -> u64 {
+        self.percentile(0.999)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,168 @@
+mod histogram;
+mod workload;
+
+pub use histogram::LatencyHistogram;
+pub use workload::Workload;
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+use splinterdb_rs::KvsbDB;
+use std::time::{Duration, Instant};
+
+/// How long a single benchmark invocation should run for.
+#[derive(Clone, Copy)]
+pub enum RunLength {
+    Ops(u64),
+    Duration(Duration),
+}
+
+/// Parameters for one benchmark run, mirroring RocksDB's `db_bench` knobs.
+pub struct BenchConfig {
+    pub workload: Workload,
+    pub key_size: usize,
+    pub value_size: usize,
+    pub threads: u8,
+    pub run_length: RunLength,
+    pub seed: u64,
+    pub report_interval: Duration,
+}
+
+/// Throughput and latency for one benchmark run.
+pub struct BenchReport {
+    pub ops_completed: u64,
+    pub elapsed: Duration,
+    pub latencies: LatencyHistogram,
+}
+
+impl BenchReport {
+    pub fn throughput_ops_per_sec(&self) -> f64 {
+        self.ops_completed as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Drives `db` with `config.workload` across `config.threads` threads,
+/// reporting live-bytes-vs-space-used (the same space-amplification line
+/// `assert-repro`'s ad-hoc loop printed) every `config.report_interval`.
+pub fn run(db: &KvsbDB, config: &BenchConfig, data_file_path: &str) -> BenchReport {
+    let initial_ops_per_chunk = match config.run_length {
+        // Each thread runs `total` ops of its own; splitting them into
+        // `threads` chunks gives it points between chunks to log the
+        // space-amplification line.
+        RunLength::Ops(total) => (total / config.threads.max(1) as u64).max(1),
+        // Duration-bounded runs still need a chunk size so each thread can
+        // check the clock periodically instead of counting ops. This is
+        // just the starting guess; `run_one_thread` retargets it after
+        // every chunk so it converges on chunks that take about as long
+        // as `report_interval`, instead of running the whole interval
+        // blind on a fixed guess.
+        RunLength::Duration(_) => 1_000,
+    };
+
+    let deadline = match config.run_length {
+        RunLength::Duration(d) => Some(Instant::now() + d),
+        RunLength::Ops(_) => None,
+    };
+
+    let start = Instant::now();
+    let per_thread_reports: Vec<(u64, LatencyHistogram)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..config.threads.max(1))
+            .map(|thread_idx| {
+                let seed = config.seed.wrapping_add(thread_idx as u64);
+                scope.spawn(move || {
+                    run_one_thread(db, config, seed, initial_ops_per_chunk, deadline, data_file_path)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("benchmark thread panicked"))
+            .collect()
+    });
+
+    let mut report = BenchReport {
+        ops_completed: 0,
+        elapsed: start.elapsed(),
+        latencies: LatencyHistogram::new(),
+    };
+    for (ops, hist) in per_thread_reports {
+        report.ops_completed += ops;
+        report.latencies.merge(&hist);
+    }
+    report
+}
+
+fn run_one_thread(
+    db: &KvsbDB,
+    config: &BenchConfig,
+    seed: u64,
+    initial_ops_per_chunk: u64,
+    deadline: Option<Instant>,
+    data_file_path: &str,
+) -> (u64, LatencyHistogram) {
+    let mut rng = Pcg64::seed_from_u64(seed);
+    let mut hist = LatencyHistogram::new();
+    let mut inserted_keys = Vec::new();
+    let mut ops_completed = 0u64;
+    let mut ops_per_chunk = initial_ops_per_chunk;
+    let mut last_report = Instant::now();
+
+    loop {
+        let chunk_start = Instant::now();
+        // Never run past an op-bounded run's per-thread total.
+        let chunk_ops = match config.run_length {
+            RunLength::Ops(total) => ops_per_chunk.min(total - ops_completed),
+            RunLength::Duration(_) => ops_per_chunk,
+        };
+        workload::run_ops(
+            config.workload,
+            db,
+            chunk_ops,
+            config.key_size,
+            config.value_size,
+            &mut rng,
+            &mut hist,
+            &mut inserted_keys,
+        );
+        ops_completed += chunk_ops;
+
+        if last_report.elapsed() >= config.report_interval {
+            report_space_amplification(data_file_path, inserted_keys.len(), config);
+            last_report = Instant::now();
+        }
+
+        // Retarget the chunk size from the throughput just observed, so a
+        // duration-bounded run's clock check converges on roughly
+        // `report_interval` per chunk instead of running blind on
+        // `initial_ops_per_chunk` for the whole run.
+        if matches!(config.run_length, RunLength::Duration(_)) {
+            let chunk_elapsed = chunk_start.elapsed();
+            if chunk_elapsed > Duration::ZERO {
+                let retarget_ratio = config.report_interval.as_secs_f64() / chunk_elapsed.as_secs_f64();
+                let retargeted = (ops_per_chunk as f64 * retarget_ratio) as u64;
+                ops_per_chunk = retargeted.clamp(1, 10_000_000);
+            }
+        }
+
+        let done = match (config.run_length, deadline) {
+            (RunLength::Ops(total), _) => ops_completed >= total,
+            (RunLength::Duration(_), Some(deadline)) => Instant::now() >= deadline,
+            (RunLength::Duration(_), None) => unreachable!("duration run always has a deadline"),
+        };
+        if done {
+            break;
+        }
+    }
+
+    (ops_completed, hist)
+}
+
+fn report_space_amplification(data_file_path: &str, live_keys: usize, config: &BenchConfig) {
+    let tuple_size_on_disk = 1 + config.key_size + config.value_size + 8;
+    let live_tuple_bytes = tuple_size_on_disk * live_keys;
+    let actual_space_used = crate::get_splinter_bytes_used(data_file_path);
+    eprintln!(
+        "bench: live data: {:06} MiB, space used: {:06} MiB",
+        live_tuple_bytes / crate::MEGA,
+        actual_space_used / crate::MEGA,
+    );
+}
@@ -0,0 +1,110 @@
+use super::histogram::LatencyHistogram;
+use crate::rand_fill_buffer;
+use crate::range_delete::RangeDeleteExt;
+use clap::ValueEnum;
+use rand::Rng;
+use rand_pcg::Pcg64;
+use splinterdb_rs::KvsbDB;
+use std::time::Instant;
+
+/// A named access pattern, mirroring RocksDB's `db_bench` workloads.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Workload {
+    /// Insert keys drawn uniformly at random.
+    FillRandom,
+    /// Insert keys in ascending order.
+    FillSeq,
+    /// Look up keys drawn uniformly at random from the keys inserted so far.
+    ReadRandom,
+    /// Interleave random reads and random inserts on the same thread.
+    ReadWhileWriting,
+    /// Scan forward from a random start key.
+    RangeScan,
+    /// Delete every key between two random existing keys.
+    DeleteRange,
+}
+
+/// Runs `ops` operations of `workload` against `db`, recording per-op
+/// latency into `hist`. `fillseq` numbers its next key from
+/// `inserted_keys.len()`; each thread keeps its own `inserted_keys`, so
+/// threads running `fillseq` write the same key sequence.
+#[allow(clippy::too_many_arguments)]
+pub fn run_ops(
+    workload: Workload,
+    db: &KvsbDB,
+    ops: u64,
+    key_size: usize,
+    value_size: usize,
+    rng: &mut Pcg64,
+    hist: &mut LatencyHistogram,
+    inserted_keys: &mut Vec<Vec<u8>>,
+) {
+    let mut key_buffer = vec![0u8; key_size];
+    let mut value_buffer = vec![0u8; value_size];
+
+    for _ in 0..ops {
+        let start = Instant::now();
+        match workload {
+            Workload::FillRandom => {
+                rand_fill_buffer(rng, &mut key_buffer);
+                rand_fill_buffer(rng, &mut value_buffer);
+                db.insert(&key_buffer, &value_buffer).unwrap();
+                inserted_keys.push(key_buffer.clone());
+            }
+            Workload::FillSeq => {
+                let next = inserted_keys.len() as u64;
+                key_buffer[..8].copy_from_slice(&next.to_be_bytes());
+                rand_fill_buffer(rng, &mut value_buffer);
+                db.insert(&key_buffer, &value_buffer).unwrap();
+                inserted_keys.push(key_buffer.clone());
+            }
+            Workload::ReadRandom => {
+                if let Some(key) = pick_existing_key(inserted_keys, rng) {
+                    db.lookup(key).unwrap();
+                }
+            }
+            Workload::ReadWhileWriting => {
+                if rng.gen_bool(0.5) {
+                    if let Some(key) = pick_existing_key(inserted_keys, rng) {
+                        db.lookup(key).unwrap();
+                    }
+                } else {
+                    rand_fill_buffer(rng, &mut key_buffer);
+                    rand_fill_buffer(rng, &mut value_buffer);
+                    db.insert(&key_buffer, &value_buffer).unwrap();
+                    inserted_keys.push(key_buffer.clone());
+                }
+            }
+            Workload::RangeScan => {
+                if let Some(start_key) = pick_existing_key(inserted_keys, rng) {
+                    let mut iter = db.range(Some(start_key)).unwrap();
+                    while let Ok(Some(_)) = iter.next() {}
+                }
+            }
+            Workload::DeleteRange => {
+                let bound_keys = (
+                    pick_existing_key(inserted_keys, rng).cloned(),
+                    pick_existing_key(inserted_keys, rng).cloned(),
+                );
+                if let (Some(a), Some(b)) = bound_keys {
+                    let (start, end) = if a <= b { (a, b) } else { (b, a) };
+                    db.delete_range(Some(&start), Some(&end)).unwrap();
+                    // delete_range covers [start, end) -- drop exactly that
+                    // slice from our own bookkeeping so the space-amp line
+                    // in `report_space_amplification` doesn't keep counting
+                    // tuples that are no longer actually in the database.
+                    inserted_keys.retain(|k| *k < start || *k >= end);
+                }
+            }
+        }
+        hist.record(start.elapsed());
+    }
+}
+
+fn pick_existing_key<'a>(keys: &'a [Vec<u8>], rng: &mut Pcg64) -> Option<&'a Vec<u8>> {
+    if keys.is_empty() {
+        None
+    } else {
+        Some(&keys[rng.gen_range(0..keys.len())])
+    }
+}
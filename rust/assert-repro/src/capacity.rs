@@ -0,0 +1,36 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+
+/// Extends the sparse data file backing a *closed* database to
+/// `new_disk_size_bytes`, so the next `db_create`/`db_open` of it sees the
+/// larger capacity.
+///
+/// This does NOT implement the online `KvsbDB::grow_disk` the request
+/// asked for, and should not be read as closing that request: growing a
+/// *live* database means extending the allocator's addressable region and
+/// persisting an updated superblock while SplinterDB keeps running against
+/// it, and both of those live inside SplinterDB/`splinterdb_rs`, which this
+/// crate doesn't vendor or otherwise have write access to. All this
+/// function can do from outside is `ftruncate` the backing file while
+/// nothing has it open, which only takes effect on the *next* `db_create`/
+/// `db_open` -- an operator hitting the disk-full condition this repro
+/// reproduces still has to stop the database to use it. The request
+/// remains open.
+///
+/// Returns an error if `new_disk_size_bytes` is not larger than the file's
+/// current size.
+pub fn grow_disk_file<P: AsRef<Path>>(path: P, new_disk_size_bytes: u64) -> io::Result<()> {
+    let file = OpenOptions::new().write(true).open(path.as_ref())?;
+    let current_size = file.metadata()?.len();
+    if new_disk_size_bytes <= current_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "grow_disk_file: new size {} is not larger than current size {}",
+                new_disk_size_bytes, current_size
+            ),
+        ));
+    }
+    file.set_len(new_disk_size_bytes)
+}
@@ -0,0 +1,44 @@
+/// Compaction tuning knobs `DBConfig` doesn't expose yet.
+///
+/// `splinterdb_rs::DBConfig` has no compaction-policy fields today, so
+/// there's nowhere to actually plumb these into SplinterDB from here. This
+/// is the shape such a `DBConfig` addition would take, plus the one thing
+/// that's computable without it: a size check callers can use to see a
+/// branch approaching the oversized-compaction condition before it trips
+/// the assert, instead of only finding out from a crash.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactionPolicy {
+    /// A branch (SST) is compacted once it would exceed this size.
+    pub max_branch_size_bytes: u64,
+    /// How often, in ops, to scan for compaction candidates.
+    pub scan_interval_ops: u32,
+    /// Whether reclaiming a range tombstone requires reading the tuple
+    /// data it covers, rather than being satisfied from the index alone.
+    pub needs_data_for_delete: bool,
+}
+
+impl Default for CompactionPolicy {
+    fn default() -> Self {
+        CompactionPolicy {
+            max_branch_size_bytes: 1024 * 1024 * 1024,
+            scan_interval_ops: 10_000,
+            needs_data_for_delete: true,
+        }
+    }
+}
+
+/// Returns `true` if writing `incoming_bytes` more into a branch already
+/// holding `current_branch_bytes` would exceed `policy.max_branch_size_bytes`
+/// -- the condition behind the oversized-compaction assert this repro hits.
+///
+/// There's no per-branch byte count available from `splinterdb_rs`, so
+/// callers can only approximate `current_branch_bytes`/`incoming_bytes`
+/// from what they track themselves; this function just does the threshold
+/// comparison.
+pub fn would_exceed_branch_size(
+    policy: &CompactionPolicy,
+    current_branch_bytes: u64,
+    incoming_bytes: u64,
+) -> bool {
+    current_branch_bytes + incoming_bytes > policy.max_branch_size_bytes
+}
@@ -0,0 +1,62 @@
+use splinterdb_rs::{IteratorResult, RangeIterator};
+
+/// The result of one [`BatchedRangeIteratorExt::next_batch`] call.
+pub struct Batch {
+    /// Key/value pairs collected this call, in iteration order.
+    pub items: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Whether the underlying range has no more tuples beyond `items`.
+    pub end_of_range: bool,
+    /// Set if `next()` errored partway through the batch. `items` still
+    /// holds whatever was collected before the error, per the batching
+    /// contract: a failed fetch shouldn't throw away tuples already in
+    /// hand.
+    pub error: Option<String>,
+}
+
+/// Extends [`RangeIterator`] with a batched fetch, to amortize the FFI
+/// crossing and bounds check `next()` pays per tuple.
+///
+/// `splinterdb_rs` has no batched C-level fetch to forward to, so this
+/// still calls the underlying `next()` once per tuple under the hood --
+/// it doesn't cut the FFI crossings a true pushed-down batch fetch would.
+/// What it does give callers is one call that walks up to `max` tuples
+/// instead of hand-rolling that loop (as `do_range_delete` still does) at
+/// every call site.
+pub trait BatchedRangeIteratorExt {
+    /// Advances the iterator and copies up to `max` key/value pairs out of
+    /// it. Stops early, with a short batch, at the end of the range or on
+    /// error. Borrowed slices from the underlying iterator are only valid
+    /// until the next `next`/`next_batch` call, so this copies everything
+    /// out into owned `Vec`s rather than returning borrows.
+    fn next_batch(&mut self, max: usize) -> Batch;
+}
+
+impl<'a> BatchedRangeIteratorExt for RangeIterator<'a> {
+    fn next_batch(&mut self, max: usize) -> Batch {
+        let mut items = Vec::with_capacity(max);
+        let mut end_of_range = false;
+        let mut error = None;
+
+        for _ in 0..max {
+            match self.next() {
+                Ok(Some(&IteratorResult { key, value })) => {
+                    items.push((key.to_vec(), value.to_vec()));
+                }
+                Ok(None) => {
+                    end_of_range = true;
+                    break;
+                }
+                Err(e) => {
+                    error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        Batch {
+            items,
+            end_of_range,
+            error,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::iter_batch::BatchedRangeIteratorExt;
+use splinterdb_rs::KvsbDB;
+use std::fmt;
+
+/// How many tuples `collect_range_keys` pulls from the iterator per
+/// `next_batch` call.
+const COLLECT_BATCH_SIZE: usize = 256;
+
+/// Extends [`KvsbDB`] with a client-side, single-call bounded range-delete.
+///
+/// This is not the push-down the request asked for: `KvsbDB` is defined in
+/// `splinterdb_rs`, so nothing here can add a real method to it, and
+/// `splinterdb_rs` has no bulk range-delete to call into either.
+/// `delete_range` still issues one `delete` FFI call per tuple -- the same
+/// count `do_range_delete` always made -- and is not atomic: a delete
+/// failing partway through leaves everything before it gone and everything
+/// from it on still live, with no rollback. `Err` reports exactly where it
+/// stopped so callers aren't left guessing. Like `do_range_delete` (which
+/// deletes through `delete_keys`), it issues no `lookup` before each
+/// `delete`; what it adds is a single call instead of a hand-rolled
+/// collect/delete split.
+pub trait RangeDeleteExt {
+    /// Deletes every tuple with a key in `[start, end)` and returns the
+    /// number of tuples removed. `start = None` means "from the first key";
+    /// `end = None` means "through the last key".
+    fn delete_range(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> Result<usize, RangeDeleteError>;
+}
+
+impl RangeDeleteExt for KvsbDB {
+    fn delete_range(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> Result<usize, RangeDeleteError> {
+        let keys = collect_range_keys(self, start, end).map_err(|source| RangeDeleteError {
+            deleted_so_far: 0,
+            failed_key: None,
+            source,
+        })?;
+
+        for (deleted_so_far, key) in keys.iter().enumerate() {
+            if let Err(e) = self.delete(key) {
+                return Err(RangeDeleteError {
+                    deleted_so_far,
+                    failed_key: Some(key.clone()),
+                    source: e.to_string(),
+                });
+            }
+        }
+        Ok(keys.len())
+    }
+}
+
+/// Why a `delete_range` call stopped before covering the whole range.
+/// There's no rollback for deletes that already landed, so `deleted_so_far`
+/// is how a caller finds out how much of the range is gone versus still
+/// live rather than just seeing a generic failure.
+#[derive(Debug)]
+pub struct RangeDeleteError {
+    /// Tuples successfully deleted before `source` occurred.
+    pub deleted_so_far: usize,
+    /// The key whose `delete` call failed, or `None` if the failure was in
+    /// collecting candidate keys, before any delete was issued.
+    pub failed_key: Option<Vec<u8>>,
+    source: String,
+}
+
+impl fmt::Display for RangeDeleteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failed_key {
+            Some(key) => write!(
+                f,
+                "delete_range stopped after {} tuple(s): delete of key {:?} failed: {}",
+                self.deleted_so_far, key, self.source
+            ),
+            None => write!(
+                f,
+                "delete_range deleted 0 tuples: range iteration failed: {}",
+                self.source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RangeDeleteError {}
+
+/// Collects every key in `[start, end)`. Scoped separately from the delete
+/// pass above: the RAII iterator must be dropped before we start deleting
+/// out from underneath it.
+fn collect_range_keys(db: &KvsbDB, start: Option<&[u8]>, end: Option<&[u8]>) -> Result<Vec<Vec<u8>>, String> {
+    let mut keys = Vec::new();
+    let mut iter = db.range(start).unwrap();
+    loop {
+        let batch = iter.next_batch(COLLECT_BATCH_SIZE);
+        if let Some(e) = batch.error {
+            return Err(e);
+        }
+
+        let mut hit_end = false;
+        for (key, _value) in batch.items {
+            if let Some(end) = end {
+                if key >= end {
+                    hit_end = true;
+                    break;
+                }
+            }
+            keys.push(key);
+        }
+
+        if hit_end || batch.end_of_range {
+            break;
+        }
+    }
+    Ok(keys)
+}
+
+/// Deletes exactly the given keys, with no existence check beforehand: the
+/// caller just read them off a live iterator, so a lookup first only pays
+/// for a second round trip to confirm what we already know.
+pub(crate) fn delete_keys(db: &KvsbDB, keys: &[Vec<u8>]) -> usize {
+    for key in keys {
+        db.delete(key).unwrap();
+    }
+    keys.len()
+}